@@ -0,0 +1,542 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use rusqlite::{params, Connection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::decompress_blob;
+
+/// Output format for `export_conversations`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+/// Which conversations/messages to include in an export.
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    /// Only include messages created at or after this Unix timestamp.
+    pub since: Option<i64>,
+    /// Only include messages created at or before this Unix timestamp.
+    pub until: Option<i64>,
+    /// Only include messages authored by this role (e.g. "user", "assistant").
+    pub author_role: Option<String>,
+    /// Include conversations marked `is_archived`.
+    pub include_archived: bool,
+    /// Only include messages on the active branch (see `is_on_active_path`).
+    pub active_path_only: bool,
+}
+
+/// How asset bytes should be represented in the export.
+#[derive(Debug, Clone)]
+pub enum AssetHandling {
+    /// Inline assets as base64 `data:` URLs so a single file is self-contained.
+    Inline,
+    /// Extract assets as files under this directory, referenced by relative path.
+    Extract(PathBuf),
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ExportedConversation {
+    id: String,
+    title: String,
+    create_time: i64,
+    update_time: i64,
+    model_slug: Option<String>,
+    is_archived: bool,
+    messages: Vec<ExportedMessage>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ExportedMessage {
+    id: String,
+    author_role: String,
+    content_type: String,
+    text_content: Option<String>,
+    create_time: Option<i64>,
+    branch_id: i64,
+    is_on_active_path: bool,
+    assets: Vec<ExportedAsset>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ExportedAsset {
+    file_name: String,
+    mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+}
+
+/// Reads conversations matching `filter` back out of the database and
+/// writes one file per conversation into `output_dir` in the given
+/// `format`. Returns the number of conversations exported.
+pub fn export_conversations(
+    conn: &Connection,
+    format: ExportFormat,
+    filter: &ExportFilter,
+    assets: &AssetHandling,
+    output_dir: &Path,
+) -> Result<usize> {
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create output directory: {}", output_dir.display()))?;
+
+    if let AssetHandling::Extract(assets_dir) = assets {
+        fs::create_dir_all(assets_dir)
+            .with_context(|| format!("Failed to create assets directory: {}", assets_dir.display()))?;
+    }
+
+    let conversations = fetch_conversations(conn, filter, assets)?;
+
+    for conversation in &conversations {
+        let (extension, rendered) = match format {
+            ExportFormat::Markdown => ("md", render_markdown(conversation)),
+            ExportFormat::Json => (
+                "json",
+                serde_json::to_string_pretty(conversation).context("Failed to serialize conversation")?,
+            ),
+        };
+
+        let path = output_dir.join(format!("{}.{}", sanitize_filename(&conversation.id), extension));
+        fs::write(&path, rendered).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(conversations.len())
+}
+
+fn fetch_conversations(
+    conn: &Connection,
+    filter: &ExportFilter,
+    assets: &AssetHandling,
+) -> Result<Vec<ExportedConversation>> {
+    let mut conv_stmt = conn.prepare(
+        "SELECT id, title, create_time, update_time, model_slug, is_archived FROM conversations \
+         WHERE is_archived = 0 OR ?1 ORDER BY create_time",
+    )?;
+
+    let conversation_rows = conv_stmt
+        .query_map(params![filter.include_archived], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, bool>(5)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read conversations")?;
+
+    let mut conversations = Vec::new();
+
+    for (id, title, create_time, update_time, model_slug, is_archived) in conversation_rows {
+        let messages = fetch_messages(conn, &id, filter, assets)?;
+
+        conversations.push(ExportedConversation {
+            id,
+            title,
+            create_time,
+            update_time,
+            model_slug,
+            is_archived,
+            messages,
+        });
+    }
+
+    Ok(conversations)
+}
+
+fn fetch_messages(
+    conn: &Connection,
+    conversation_id: &str,
+    filter: &ExportFilter,
+    assets: &AssetHandling,
+) -> Result<Vec<ExportedMessage>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, author_role, content_type, text_content, create_time, branch_id, is_on_active_path \
+         FROM messages \
+         WHERE conversation_id = ?1 \
+           AND (?2 IS NULL OR create_time >= ?2) \
+           AND (?3 IS NULL OR create_time <= ?3) \
+           AND (?4 IS NULL OR author_role = ?4) \
+           AND (?5 = 0 OR is_on_active_path = 1) \
+         ORDER BY message_order",
+    )?;
+
+    let rows = stmt
+        .query_map(
+            params![
+                conversation_id,
+                filter.since,
+                filter.until,
+                filter.author_role,
+                filter.active_path_only
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, bool>(6)?,
+                ))
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read messages")?;
+
+    let mut messages = Vec::new();
+
+    for (id, author_role, content_type, text_content, create_time, branch_id, is_on_active_path) in rows {
+        let message_assets = fetch_assets(conn, &id, assets)?;
+
+        messages.push(ExportedMessage {
+            id,
+            author_role,
+            content_type,
+            text_content,
+            create_time,
+            branch_id,
+            is_on_active_path,
+            assets: message_assets,
+        });
+    }
+
+    Ok(messages)
+}
+
+fn fetch_assets(conn: &Connection, message_id: &str, assets: &AssetHandling) -> Result<Vec<ExportedAsset>> {
+    let mut stmt = conn.prepare(
+        "SELECT a.file_name, a.mime_type, b.file_content, b.compression \
+         FROM assets a LEFT JOIN blobs b ON b.sha256 = a.blob_sha256 \
+         WHERE a.message_id = ?1 ORDER BY a.asset_order",
+    )?;
+
+    let rows = stmt
+        .query_map(params![message_id], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<Vec<u8>>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read assets")?;
+
+    let mut exported = Vec::new();
+
+    for (file_name, mime_type, file_content, compression) in rows {
+        let file_name = file_name.unwrap_or_default();
+        let mime_type = mime_type.unwrap_or_else(|| "application/octet-stream".to_string());
+
+        let bytes = match (file_content, compression) {
+            (Some(bytes), Some(compression)) => Some(decompress_blob(&bytes, &compression)?),
+            _ => None,
+        };
+
+        let (data_url, path) = match (bytes, assets) {
+            (Some(bytes), AssetHandling::Inline) => (Some(to_data_url(&mime_type, &bytes)), None),
+            (Some(bytes), AssetHandling::Extract(assets_dir)) => {
+                let path = write_asset_file(assets_dir, message_id, &file_name, &bytes)?;
+                (None, Some(path))
+            }
+            (None, _) => (None, None),
+        };
+
+        exported.push(ExportedAsset {
+            file_name,
+            mime_type,
+            data_url,
+            path,
+        });
+    }
+
+    Ok(exported)
+}
+
+fn write_asset_file(assets_dir: &Path, message_id: &str, file_name: &str, bytes: &[u8]) -> Result<String> {
+    let message_id = sanitize_filename(message_id);
+    let name = if file_name.is_empty() {
+        message_id
+    } else {
+        format!("{}-{}", message_id, sanitize_filename(file_name))
+    };
+
+    let path = assets_dir.join(&name);
+    fs::write(&path, bytes).with_context(|| format!("Failed to write asset file: {}", path.display()))?;
+
+    Ok(format!("assets/{}", name))
+}
+
+fn to_data_url(mime_type: &str, bytes: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    format!("data:{};base64,{}", mime_type, encoded)
+}
+
+fn render_markdown(conversation: &ExportedConversation) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("# {}\n\n", conversation.title));
+    out.push_str(&format!("- Conversation ID: `{}`\n", conversation.id));
+    if let Some(model_slug) = &conversation.model_slug {
+        out.push_str(&format!("- Model: {}\n", model_slug));
+    }
+    if conversation.is_archived {
+        out.push_str("- Archived: yes\n");
+    }
+    out.push('\n');
+
+    for message in &conversation.messages {
+        out.push_str(&format!("## {}\n\n", message.author_role));
+
+        if let Some(text) = &message.text_content {
+            out.push_str(text);
+            out.push_str("\n\n");
+        }
+
+        for asset in &message.assets {
+            let src = asset
+                .data_url
+                .clone()
+                .or_else(|| asset.path.clone())
+                .unwrap_or_default();
+            out.push_str(&format!("![{}]({})\n\n", asset.file_name, src));
+        }
+    }
+
+    out
+}
+
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        let schema = fs::read_to_string("schema.sql").unwrap();
+        conn.execute_batch(&schema).unwrap();
+        conn
+    }
+
+    fn insert_conversation(conn: &Connection, id: &str, title: &str, is_archived: bool) {
+        conn.execute(
+            "INSERT INTO conversations (id, title, create_time, update_time, model_slug, is_archived) \
+             VALUES (?1, ?2, 0, 0, NULL, ?3)",
+            params![id, title, is_archived],
+        )
+        .unwrap();
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert_message(
+        conn: &Connection,
+        id: &str,
+        conversation_id: &str,
+        author_role: &str,
+        text_content: &str,
+        create_time: i64,
+        message_order: i64,
+        is_on_active_path: bool,
+    ) {
+        conn.execute(
+            "INSERT INTO messages (id, conversation_id, parent_id, author_role, content_type, text_content, \
+             create_time, model_slug, message_order, has_assets, branch_id, is_on_active_path) \
+             VALUES (?1, ?2, NULL, ?3, 'text', ?4, ?5, NULL, ?6, 0, 0, ?7)",
+            params![
+                id,
+                conversation_id,
+                author_role,
+                text_content,
+                create_time,
+                message_order,
+                is_on_active_path
+            ],
+        )
+        .unwrap();
+    }
+
+    fn temp_output_dir(label: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "importer-test-export-{}-{}",
+            label,
+            std::process::id()
+        ))
+    }
+
+    fn cleanup(dir: &Path) {
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn filter_excludes_archived_messages_outside_role_and_time_window_and_inactive_branch() {
+        let conn = test_db();
+        insert_conversation(&conn, "c1", "Kept", false);
+        insert_message(&conn, "m1", "c1", "user", "in window", 100, 0, true);
+        insert_message(&conn, "m2", "c1", "assistant", "wrong role target", 100, 1, true);
+        insert_message(&conn, "m3", "c1", "user", "too early", 0, 2, true);
+        insert_message(&conn, "m4", "c1", "user", "inactive draft", 100, 3, false);
+
+        insert_conversation(&conn, "c2", "Archived", true);
+        insert_message(&conn, "m5", "c2", "user", "archived message", 100, 0, true);
+
+        let out_dir = temp_output_dir("filter");
+        let filter = ExportFilter {
+            since: Some(50),
+            until: Some(200),
+            author_role: Some("user".to_string()),
+            include_archived: false,
+            active_path_only: true,
+        };
+
+        let count = export_conversations(&conn, ExportFormat::Json, &filter, &AssetHandling::Inline, &out_dir)
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let rendered = fs::read_to_string(out_dir.join("c1.json")).unwrap();
+        let conversation: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let ids: Vec<&str> = conversation["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["m1"]);
+
+        cleanup(&out_dir);
+    }
+
+    #[test]
+    fn include_archived_pulls_in_archived_conversations() {
+        let conn = test_db();
+        insert_conversation(&conn, "c1", "Archived", true);
+        insert_message(&conn, "m1", "c1", "user", "hello", 0, 0, true);
+
+        let out_dir = temp_output_dir("archived");
+        let filter = ExportFilter {
+            include_archived: true,
+            ..Default::default()
+        };
+
+        let count = export_conversations(&conn, ExportFormat::Json, &filter, &AssetHandling::Inline, &out_dir)
+            .unwrap();
+        assert_eq!(count, 1);
+
+        cleanup(&out_dir);
+    }
+
+    #[test]
+    fn markdown_rendering_includes_title_role_and_text() {
+        let conn = test_db();
+        insert_conversation(&conn, "c1", "My Conversation", false);
+        insert_message(&conn, "m1", "c1", "user", "hello there", 0, 0, true);
+
+        let out_dir = temp_output_dir("markdown");
+        let count = export_conversations(
+            &conn,
+            ExportFormat::Markdown,
+            &ExportFilter::default(),
+            &AssetHandling::Inline,
+            &out_dir,
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+
+        let rendered = fs::read_to_string(out_dir.join("c1.md")).unwrap();
+        assert!(rendered.contains("# My Conversation"));
+        assert!(rendered.contains("## user"));
+        assert!(rendered.contains("hello there"));
+
+        cleanup(&out_dir);
+    }
+
+    #[test]
+    fn inline_asset_handling_embeds_a_data_url_with_the_stored_mime_type() {
+        let conn = test_db();
+        insert_conversation(&conn, "c1", "With Asset", false);
+        insert_message(&conn, "m1", "c1", "user", "see attached", 0, 0, true);
+
+        conn.execute(
+            "INSERT INTO blobs (sha256, file_content, size_bytes, compression) VALUES ('deadbeef', ?1, 5, 'none')",
+            params![b"hello".to_vec()],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO assets (id, message_id, asset_pointer, content_type, size_bytes, width, height, \
+             metadata, asset_order, blob_sha256, file_name, mime_type) \
+             VALUES ('a1', 'm1', 'file-a1', 'image_asset_pointer', 5, NULL, NULL, NULL, 0, 'deadbeef', 'photo.png', 'image/png')",
+            [],
+        )
+        .unwrap();
+
+        let out_dir = temp_output_dir("inline-asset");
+        export_conversations(
+            &conn,
+            ExportFormat::Json,
+            &ExportFilter::default(),
+            &AssetHandling::Inline,
+            &out_dir,
+        )
+        .unwrap();
+
+        let rendered = fs::read_to_string(out_dir.join("c1.json")).unwrap();
+        let conversation: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        let data_url = conversation["messages"][0]["assets"][0]["data_url"].as_str().unwrap();
+        assert_eq!(data_url, "data:image/png;base64,aGVsbG8=");
+
+        cleanup(&out_dir);
+    }
+
+    #[test]
+    fn extracted_asset_file_names_are_sanitized_and_cannot_escape_the_assets_dir() {
+        let conn = test_db();
+        insert_conversation(&conn, "c1", "With Asset", false);
+        insert_message(&conn, "../../../etc/cron.d/evil", "c1", "user", "see attached", 0, 0, true);
+
+        conn.execute(
+            "INSERT INTO blobs (sha256, file_content, size_bytes, compression) VALUES ('deadbeef', ?1, 5, 'none')",
+            params![b"hello".to_vec()],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO assets (id, message_id, asset_pointer, content_type, size_bytes, width, height, \
+             metadata, asset_order, blob_sha256, file_name, mime_type) \
+             VALUES ('a1', '../../../etc/cron.d/evil', 'file-a1', 'image_asset_pointer', 5, NULL, NULL, NULL, 0, 'deadbeef', 'photo.png', 'image/png')",
+            [],
+        )
+        .unwrap();
+
+        let out_dir = temp_output_dir("extract-asset");
+        export_conversations(
+            &conn,
+            ExportFormat::Json,
+            &ExportFilter::default(),
+            &AssetHandling::Extract(out_dir.join("assets")),
+            &out_dir,
+        )
+        .unwrap();
+
+        let assets_dir = out_dir.join("assets");
+        let entries: Vec<String> = fs::read_dir(&assets_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+
+        assert_eq!(entries.len(), 1);
+        assert!(!entries[0].contains(".."));
+        assert!(!entries[0].contains('/'));
+
+        cleanup(&out_dir);
+    }
+}
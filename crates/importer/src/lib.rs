@@ -1,14 +1,18 @@
 use anyhow::{Context, Result};
 use rusqlite::{params, Connection};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::Read;
 use std::path::PathBuf;
 use zip::ZipArchive;
 
+pub mod export;
 pub mod models;
+pub mod search;
+pub use export::*;
 pub use models::*;
+pub use search::*;
 
 pub fn extract_conversations_from_zip(zip_path: &PathBuf) -> Result<String> {
     let file = fs::File::open(zip_path)
@@ -45,7 +49,13 @@ pub fn import_conversations(
     conn: &Connection,
     conversations: &[Conversation],
     zip_path: &PathBuf,
+    compress: bool,
 ) -> Result<()> {
+    let zip_file = fs::File::open(zip_path)
+        .with_context(|| format!("Failed to open zip file: {}", zip_path.display()))?;
+    let mut archive = ZipArchive::new(zip_file).context("Failed to read zip archive")?;
+    let asset_index = build_asset_index(&mut archive);
+
     conn.execute("PRAGMA foreign_keys = OFF", [])?;
     let mut conv_count = 0;
     let mut msg_count = 0;
@@ -69,32 +79,49 @@ pub fn import_conversations(
 
         conv_count += 1;
 
-        let messages = extract_messages_from_mapping(&conv_id, &conv.mapping);
-        for (order, (msg_id, msg, parent_id)) in messages.iter().enumerate() {
-            if should_skip_message(msg) {
-                continue;
-            }
+        let messages = extract_messages_from_mapping(&conv_id, &conv.mapping, conv.current_node.as_deref());
+        for (order, imported) in messages.iter().enumerate() {
+            let msg_id = &imported.id;
+            let msg = &imported.message;
 
             let (text_content, assets) = extract_content_and_assets(&msg.content);
             let has_assets = !assets.is_empty();
 
+            if should_skip_message(msg, text_content.as_deref(), has_assets) {
+                continue;
+            }
+
             conn.execute(
-                "INSERT OR REPLACE INTO messages (id, conversation_id, parent_id, author_role, content_type, text_content, create_time, model_slug, message_order, has_assets) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                "INSERT OR REPLACE INTO messages (id, conversation_id, parent_id, author_role, content_type, text_content, create_time, model_slug, message_order, has_assets, branch_id, is_on_active_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                 params![
                     msg_id,
                     conv_id,
-                    parent_id,
+                    imported.parent_id,
                     msg.author.role,
                     msg.content.content_type,
                     text_content,
                     msg.create_time.map(|t| t as i64),
                     extract_model_slug(msg, conv),
                     order as i32,
-                    has_assets
+                    has_assets,
+                    imported.branch_id,
+                    imported.is_on_active_path
                 ],
             )
             .with_context(|| format!("Failed to insert message: {}", msg_id))?;
 
+            conn.execute(
+                "DELETE FROM messages_fts WHERE message_id = ?1",
+                params![msg_id],
+            )
+            .with_context(|| format!("Failed to clear stale search index for message: {}", msg_id))?;
+
+            conn.execute(
+                "INSERT INTO messages_fts (conversation_id, message_id, title, author_role, text_content) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![conv_id, msg_id, conv.title, msg.author.role, text_content],
+            )
+            .with_context(|| format!("Failed to index message for search: {}", msg_id))?;
+
             msg_count += 1;
 
             for (asset_order, asset) in assets.iter().enumerate() {
@@ -105,10 +132,13 @@ pub fn import_conversations(
                     .map(|m| serde_json::to_string(m).unwrap_or_default());
 
                 let (file_content, file_name, mime_type) =
-                    extract_file_from_zip(zip_path, &asset.asset_pointer)?;
+                    extract_file_from_zip(&mut archive, &asset_index, &asset.asset_pointer)?;
+
+                let blob_sha256 = store_blob(conn, &file_content, &mime_type, compress)
+                    .with_context(|| format!("Failed to store blob for asset: {}", asset_id))?;
 
                 conn.execute(
-                    "INSERT OR REPLACE INTO assets (id, message_id, asset_pointer, content_type, size_bytes, width, height, metadata, asset_order, file_content, file_name, mime_type) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                    "INSERT OR REPLACE INTO assets (id, message_id, asset_pointer, content_type, size_bytes, width, height, metadata, asset_order, blob_sha256, file_name, mime_type) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                     params![
                         asset_id,
                         msg_id,
@@ -119,7 +149,7 @@ pub fn import_conversations(
                         asset.height,
                         metadata_json,
                         asset_order as i32,
-                        file_content,
+                        blob_sha256,
                         file_name,
                         mime_type
                     ],
@@ -141,13 +171,31 @@ pub fn import_conversations(
 }
 
 fn extract_content_and_assets(content: &Content) -> (Option<String>, Vec<AssetPointer>) {
-    let mut text_content = None;
+    match parse_content_body(content) {
+        ContentBody::Text { parts } | ContentBody::MultimodalText { parts } => extract_parts(&parts),
+        ContentBody::Code { text, language } => {
+            (Some(format_code_block(&text, language.as_deref())), Vec::new())
+        }
+        ContentBody::ExecutionOutput { text } => (Some(text), Vec::new()),
+        ContentBody::TetherBrowsingDisplay { result, summary } => (result.or(summary), Vec::new()),
+        ContentBody::TetherQuote { text, title, .. } => (text.or(title), Vec::new()),
+        ContentBody::Thoughts { thoughts, .. } => (format_thoughts(&thoughts), Vec::new()),
+        ContentBody::ReasoningRecap { content } => (content, Vec::new()),
+        ContentBody::Unknown => extract_parts(&content.parts),
+    }
+}
+
+/// Walks a `parts` array (from `text`/`multimodal_text` content), joining
+/// every string part into one body and collecting any asset pointers mixed
+/// in among them.
+fn extract_parts(parts: &[Value]) -> (Option<String>, Vec<AssetPointer>) {
+    let mut text_parts = Vec::new();
     let mut assets = Vec::new();
 
-    for part in &content.parts {
+    for part in parts {
         match part {
             Value::String(text) => {
-                text_content = Some(text.clone());
+                text_parts.push(text.clone());
             }
             Value::Object(_) => {
                 if let Ok(asset) = serde_json::from_value::<AssetPointer>(part.clone()) {
@@ -158,9 +206,32 @@ fn extract_content_and_assets(content: &Content) -> (Option<String>, Vec<AssetPo
         }
     }
 
+    let text_content = if text_parts.is_empty() {
+        None
+    } else {
+        Some(text_parts.join("\n\n"))
+    };
+
     (text_content, assets)
 }
 
+fn format_code_block(text: &str, language: Option<&str>) -> String {
+    format!("```{}\n{}\n```", language.unwrap_or(""), text)
+}
+
+fn format_thoughts(thoughts: &[Thought]) -> Option<String> {
+    let rendered: Vec<String> = thoughts
+        .iter()
+        .filter_map(|thought| thought.content.clone().or_else(|| thought.summary.clone()))
+        .collect();
+
+    if rendered.is_empty() {
+        None
+    } else {
+        Some(rendered.join("\n\n"))
+    }
+}
+
 fn extract_asset_id(asset_pointer: &str) -> String {
     asset_pointer
         .split("file-")
@@ -169,59 +240,115 @@ fn extract_asset_id(asset_pointer: &str) -> String {
         .to_string()
 }
 
-fn extract_file_from_zip(zip_path: &PathBuf, asset_pointer: &str) -> Result<(Vec<u8>, String, String)> {
-    let file = fs::File::open(zip_path)
-        .with_context(|| format!("Failed to open zip file: {}", zip_path.display()))?;
-
-    let mut archive = ZipArchive::new(file).context("Failed to read zip archive")?;
-
-    let asset_id = extract_asset_id(asset_pointer);
+/// Scans the archive once and maps each entry's asset id to its index, so
+/// assets can be resolved with a single lookup instead of a linear rescan
+/// per asset.
+fn build_asset_index(archive: &mut ZipArchive<fs::File>) -> HashMap<String, usize> {
+    let mut index = HashMap::new();
 
     for i in 0..archive.len() {
-        let file_name = {
-            let file = archive.by_index(i)?;
-            file.name().to_string()
-        };
+        if let Ok(file) = archive.by_index(i) {
+            index.entry(zip_entry_asset_id(file.name())).or_insert(i);
+        }
+    }
 
-        if file_name.contains(&asset_id) {
-            let mut file = archive.by_index(i)?;
-            let mut content = Vec::new();
-            file.read_to_end(&mut content)
-                .with_context(|| format!("Failed to read file: {}", file_name))?;
+    index
+}
 
-            let mime_type = guess_mime_type(&file_name);
+/// Extracts the asset id a zip entry was named after, mirroring
+/// `extract_asset_id` so pointers and entry names land on the same key.
+/// ChatGPT exports name asset files `file-<id>-<description>.<ext>`.
+fn zip_entry_asset_id(file_name: &str) -> String {
+    let after_prefix = file_name.split("file-").last().unwrap_or(file_name);
+    after_prefix
+        .split(['-', '.'])
+        .next()
+        .unwrap_or(after_prefix)
+        .to_string()
+}
 
-            return Ok((content, file_name, mime_type));
+fn extract_file_from_zip(
+    archive: &mut ZipArchive<fs::File>,
+    asset_index: &HashMap<String, usize>,
+    asset_pointer: &str,
+) -> Result<(Vec<u8>, String, String)> {
+    let asset_id = extract_asset_id(asset_pointer);
+
+    let index = match asset_index.get(&asset_id) {
+        Some(index) => *index,
+        None => {
+            println!("Warning: File not found in zip for asset: {}", asset_pointer);
+            return Ok((Vec::new(), String::new(), String::new()));
         }
-    }
+    };
+
+    let mut file = archive.by_index(index)?;
+    let file_name = file.name().to_string();
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)
+        .with_context(|| format!("Failed to read file: {}", file_name))?;
+
+    let mime_type = guess_mime_type(&file_name);
 
-    println!("Warning: File not found in zip for asset: {}", asset_pointer);
-    Ok((Vec::new(), String::new(), String::new()))
+    Ok((content, file_name, mime_type))
 }
 
 fn guess_mime_type(file_name: &str) -> String {
-    let extension = file_name.split('.').last().unwrap_or("").to_lowercase();
-    match extension.as_str() {
-        "jpg" | "jpeg" => "image/jpeg".to_string(),
-        "png" => "image/png".to_string(),
-        "gif" => "image/gif".to_string(),
-        "webp" => "image/webp".to_string(),
-        "pdf" => "application/pdf".to_string(),
-        "txt" => "text/plain".to_string(),
-        "json" => "application/json".to_string(),
-        _ => "application/octet-stream".to_string(),
+    mime_guess::from_path(file_name)
+        .first_or_octet_stream()
+        .to_string()
+}
+
+/// Hashes `bytes` with SHA-256 and ensures a row for it exists in `blobs`,
+/// deduplicating identical assets referenced from multiple conversations.
+/// Returns the hex digest to store as the asset's foreign key.
+fn store_blob(conn: &Connection, bytes: &[u8], mime_type: &str, compress: bool) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(bytes);
+    let sha256 = format!("{:x}", digest);
+
+    let (stored_content, compression) = if compress && should_compress(mime_type) {
+        let compressed = zstd::encode_all(bytes, 0).context("Failed to zstd-compress blob")?;
+        (compressed, "zstd")
+    } else {
+        (bytes.to_vec(), "none")
+    };
+
+    conn.execute(
+        "INSERT OR IGNORE INTO blobs (sha256, file_content, size_bytes, compression) VALUES (?1, ?2, ?3, ?4)",
+        params![sha256, stored_content, bytes.len() as i64, compression],
+    )
+    .context("Failed to insert blob")?;
+
+    Ok(sha256)
+}
+
+/// Text and already-compressed image formats gain little from zstd and
+/// aren't worth the CPU, so only compress everything else (PDFs, other
+/// binary formats).
+fn should_compress(mime_type: &str) -> bool {
+    !(mime_type.starts_with("text/")
+        || matches!(mime_type, "image/jpeg" | "image/png" | "image/webp"))
+}
+
+/// Reverses `store_blob`'s compression so readers get the original bytes
+/// back regardless of how a blob happened to be stored.
+pub fn decompress_blob(file_content: &[u8], compression: &str) -> Result<Vec<u8>> {
+    match compression {
+        "none" => Ok(file_content.to_vec()),
+        "zstd" => zstd::decode_all(file_content).context("Failed to zstd-decompress blob"),
+        other => anyhow::bail!("Unknown blob compression scheme: {}", other),
     }
 }
 
 fn find_conversation_id(conv: &Conversation) -> String {
     for node in conv.mapping.values() {
         if let Some(msg) = &node.message {
-            if msg.author.role == "user" || msg.author.role == "assistant" {
-                if !msg.content.parts.is_empty() {
-                    if let Value::String(s) = &msg.content.parts[0] {
-                        if !s.is_empty() {
-                            return format!("conv_{}", msg.id);
-                        }
+            if (msg.author.role == "user" || msg.author.role == "assistant") && !msg.content.parts.is_empty() {
+                if let Value::String(s) = &msg.content.parts[0] {
+                    if !s.is_empty() {
+                        return format!("conv_{}", msg.id);
                     }
                 }
             }
@@ -237,13 +364,34 @@ fn find_conversation_id(conv: &Conversation) -> String {
     format!("conv_{:x}", hasher.finish())
 }
 
+/// A message recovered from the conversation tree, annotated with which
+/// regeneration/edit branch it belongs to and whether it lies on the path
+/// ChatGPT's `current_node` actually points at.
+struct ImportedMessage {
+    id: String,
+    message: Message,
+    parent_id: Option<String>,
+    branch_id: i64,
+    is_on_active_path: bool,
+}
+
 fn extract_messages_from_mapping(
     _conv_id: &str,
     mapping: &HashMap<String, MappingNode>,
-) -> Vec<(String, Message, Option<String>)> {
+    current_node: Option<&str>,
+) -> Vec<ImportedMessage> {
     let mut messages = Vec::new();
 
-    let mut visited = std::collections::HashSet::new();
+    let mut visited = HashSet::new();
+    let active_path = active_path_nodes(mapping, current_node);
+
+    // `current_node` is optional, and older/synthetic exports can also set
+    // it to a value absent from `mapping`. Either way there's no real
+    // branch information to anchor on, so treat the whole tree as active
+    // rather than marking every message as a draft.
+    let has_active_path = current_node
+        .map(|id| mapping.contains_key(id))
+        .unwrap_or(false);
 
     let root_node = mapping
         .values()
@@ -258,17 +406,49 @@ fn extract_messages_from_mapping(
         .or_else(|| mapping.get("client-created-root"));
 
     if let Some(root) = root_node {
-        traverse_messages(mapping, &root.id, &mut messages, &mut visited, None);
+        let mut next_branch_id = 0i64;
+        traverse_messages(
+            mapping,
+            &root.id,
+            0,
+            &active_path,
+            has_active_path,
+            &mut messages,
+            &mut visited,
+            &mut next_branch_id,
+            None,
+        );
     }
 
     messages
 }
 
+/// Walks from `current_node` up through `parent` pointers to collect the
+/// canonical active path (the branch ChatGPT actually shows the user).
+fn active_path_nodes(mapping: &HashMap<String, MappingNode>, current_node: Option<&str>) -> HashSet<String> {
+    let mut active = HashSet::new();
+    let mut cur = current_node;
+
+    while let Some(id) = cur {
+        if !active.insert(id.to_string()) {
+            break;
+        }
+        cur = mapping.get(id).and_then(|node| node.parent.as_deref());
+    }
+
+    active
+}
+
+#[allow(clippy::too_many_arguments)]
 fn traverse_messages(
     mapping: &HashMap<String, MappingNode>,
     node_id: &str,
-    messages: &mut Vec<(String, Message, Option<String>)>,
-    visited: &mut std::collections::HashSet<String>,
+    branch_id: i64,
+    active_path: &HashSet<String>,
+    has_active_path: bool,
+    messages: &mut Vec<ImportedMessage>,
+    visited: &mut HashSet<String>,
+    next_branch_id: &mut i64,
     parent_id: Option<String>,
 ) {
     if visited.contains(node_id) {
@@ -278,16 +458,45 @@ fn traverse_messages(
 
     if let Some(node) = mapping.get(node_id) {
         if let Some(msg) = &node.message {
-            messages.push((msg.id.clone(), msg.clone(), parent_id));
+            messages.push(ImportedMessage {
+                id: msg.id.clone(),
+                message: msg.clone(),
+                parent_id,
+                branch_id,
+                is_on_active_path: !has_active_path || active_path.contains(node_id),
+            });
         }
 
-        for child_id in &node.children {
-            traverse_messages(mapping, child_id, messages, visited, Some(node_id.to_string()));
+        // Sibling order is preserved via `children`'s existing order; the
+        // first child continues the current branch, every other child
+        // starts a new one so alternate drafts can be told apart later.
+        for (i, child_id) in node.children.iter().enumerate() {
+            let child_branch_id = if i == 0 {
+                branch_id
+            } else {
+                *next_branch_id += 1;
+                *next_branch_id
+            };
+
+            traverse_messages(
+                mapping,
+                child_id,
+                child_branch_id,
+                active_path,
+                has_active_path,
+                messages,
+                visited,
+                next_branch_id,
+                Some(node_id.to_string()),
+            );
         }
     }
 }
 
-fn should_skip_message(msg: &Message) -> bool {
+/// `text_content`/`has_assets` are the already-extracted content for this
+/// message (see `extract_content_and_assets`) so callers don't need to
+/// parse it twice.
+fn should_skip_message(msg: &Message, text_content: Option<&str>, has_assets: bool) -> bool {
     if let Some(metadata) = &msg.metadata {
         if metadata
             .get("is_visually_hidden_from_conversation")
@@ -298,17 +507,9 @@ fn should_skip_message(msg: &Message) -> bool {
         }
     }
 
-    if msg.content.parts.is_empty()
-        || msg
-            .content
-            .parts
-            .iter()
-            .all(|part| matches!(part, Value::String(s) if s.trim().is_empty()))
-    {
-        return true;
-    }
+    let has_text = text_content.map(|s| !s.trim().is_empty()).unwrap_or(false);
 
-    false
+    !has_text && !has_assets
 }
 
 fn extract_model_slug(msg: &Message, conv: &Conversation) -> Option<String> {
@@ -323,3 +524,266 @@ fn extract_model_slug(msg: &Message, conv: &Conversation) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_message(id: &str, role: &str, text: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            author: Author {
+                role: role.to_string(),
+                name: None,
+                metadata: HashMap::new(),
+            },
+            create_time: Some(0.0),
+            update_time: Some(0.0),
+            content: Content {
+                content_type: "text".to_string(),
+                parts: vec![Value::String(text.to_string())],
+                user_profile: None,
+                user_instructions: None,
+                extra: HashMap::new(),
+            },
+            status: None,
+            end_turn: None,
+            weight: None,
+            metadata: None,
+            recipient: None,
+            channel: None,
+        }
+    }
+
+    fn node(id: &str, message: Option<Message>, parent: Option<&str>, children: &[&str]) -> MappingNode {
+        MappingNode {
+            id: id.to_string(),
+            message,
+            parent: parent.map(|p| p.to_string()),
+            children: children.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    /// root -> msg1 -> (msg2a, msg2b), with msg2b as the regenerated/active draft.
+    fn forked_mapping() -> HashMap<String, MappingNode> {
+        let mut mapping = HashMap::new();
+        mapping.insert("root".to_string(), node("root", None, None, &["msg1"]));
+        mapping.insert(
+            "msg1".to_string(),
+            node("msg1", Some(text_message("msg1", "user", "hello")), Some("root"), &["msg2a", "msg2b"]),
+        );
+        mapping.insert(
+            "msg2a".to_string(),
+            node("msg2a", Some(text_message("msg2a", "assistant", "draft a")), Some("msg1"), &[]),
+        );
+        mapping.insert(
+            "msg2b".to_string(),
+            node("msg2b", Some(text_message("msg2b", "assistant", "draft b")), Some("msg1"), &[]),
+        );
+        mapping
+    }
+
+    #[test]
+    fn traverse_assigns_branch_ids_and_marks_the_active_path() {
+        let mapping = forked_mapping();
+        let messages = extract_messages_from_mapping("conv", &mapping, Some("msg2b"));
+
+        let by_id: HashMap<&str, &ImportedMessage> =
+            messages.iter().map(|m| (m.id.as_str(), m)).collect();
+
+        assert_eq!(by_id["msg1"].branch_id, 0);
+        assert!(by_id["msg1"].is_on_active_path);
+
+        assert_eq!(by_id["msg2a"].branch_id, 0);
+        assert!(!by_id["msg2a"].is_on_active_path);
+
+        assert_eq!(by_id["msg2b"].branch_id, 1);
+        assert!(by_id["msg2b"].is_on_active_path);
+    }
+
+    #[test]
+    fn missing_current_node_marks_every_message_active() {
+        let mapping = forked_mapping();
+
+        let messages = extract_messages_from_mapping("conv", &mapping, None);
+        assert!(messages.iter().all(|m| m.is_on_active_path));
+
+        let messages = extract_messages_from_mapping("conv", &mapping, Some("not-in-mapping"));
+        assert!(messages.iter().all(|m| m.is_on_active_path));
+    }
+
+    fn content_with_extra(content_type: &str, extra: &[(&str, Value)]) -> Content {
+        Content {
+            content_type: content_type.to_string(),
+            parts: Vec::new(),
+            user_profile: None,
+            user_instructions: None,
+            extra: extra.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        }
+    }
+
+    #[test]
+    fn extract_content_and_assets_dispatches_on_content_type() {
+        let text = Content {
+            content_type: "text".to_string(),
+            parts: vec![Value::String("a".to_string()), Value::String("b".to_string())],
+            user_profile: None,
+            user_instructions: None,
+            extra: HashMap::new(),
+        };
+        assert_eq!(extract_content_and_assets(&text).0.as_deref(), Some("a\n\nb"));
+
+        let code = content_with_extra(
+            "code",
+            &[
+                ("text", Value::String("print(1)".to_string())),
+                ("language", Value::String("python".to_string())),
+            ],
+        );
+        assert_eq!(
+            extract_content_and_assets(&code).0.as_deref(),
+            Some("```python\nprint(1)\n```")
+        );
+
+        let thoughts = content_with_extra(
+            "thoughts",
+            &[("thoughts", serde_json::json!([{"summary": "s1", "content": null}]))],
+        );
+        assert_eq!(extract_content_and_assets(&thoughts).0.as_deref(), Some("s1"));
+
+        let unknown = Content {
+            content_type: "some_future_type".to_string(),
+            parts: vec![Value::String("fallback".to_string())],
+            user_profile: None,
+            user_instructions: None,
+            extra: HashMap::new(),
+        };
+        assert_eq!(extract_content_and_assets(&unknown).0.as_deref(), Some("fallback"));
+    }
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        let schema = fs::read_to_string("schema.sql").unwrap();
+        conn.execute_batch(&schema).unwrap();
+        conn
+    }
+
+    #[test]
+    fn store_blob_dedups_identical_bytes_by_sha256() {
+        let conn = test_db();
+        let bytes = b"same bytes, two different assets";
+
+        let first = store_blob(&conn, bytes, "application/pdf", false).unwrap();
+        let second = store_blob(&conn, bytes, "application/pdf", false).unwrap();
+
+        assert_eq!(first, second);
+
+        let blob_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM blobs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(blob_count, 1);
+
+        let stored: Vec<u8> = conn
+            .query_row(
+                "SELECT file_content FROM blobs WHERE sha256 = ?1",
+                params![first],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored, bytes);
+    }
+
+    #[test]
+    fn zip_entry_asset_id_strips_file_prefix_and_extension() {
+        assert_eq!(zip_entry_asset_id("file-abc123-photo.png"), "abc123");
+        assert_eq!(zip_entry_asset_id("file-abc123.png"), "abc123");
+        assert_eq!(zip_entry_asset_id("plain.txt"), "plain");
+    }
+
+    fn write_test_zip(path: &std::path::Path, entries: &[(&str, &[u8])]) {
+        use std::io::Write;
+
+        let file = fs::File::create(path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        for (name, contents) in entries {
+            zip.start_file(*name, zip::write::FileOptions::default())
+                .unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn build_asset_index_maps_each_asset_id_to_its_single_entry() {
+        let path = std::env::temp_dir().join(format!(
+            "importer-test-build_asset_index-{}.zip",
+            std::process::id()
+        ));
+        write_test_zip(
+            &path,
+            &[
+                ("file-abc123-photo.png", b"photo bytes"),
+                ("file-def456-note.txt", b"note bytes"),
+                ("file-abc123-duplicate.png", b"should be ignored"),
+            ],
+        );
+
+        let file = fs::File::open(&path).unwrap();
+        let mut archive = ZipArchive::new(file).unwrap();
+        let index = build_asset_index(&mut archive);
+        fs::remove_file(&path).ok();
+
+        assert_eq!(index.get("abc123"), Some(&0));
+        assert_eq!(index.get("def456"), Some(&1));
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn store_blob_compresses_and_decompresses_back_to_original_bytes() {
+        let conn = test_db();
+        let bytes = b"not an image or text mime, should get zstd compressed".repeat(100);
+
+        let sha256 = store_blob(&conn, &bytes, "application/pdf", true).unwrap();
+
+        let (stored_content, compression): (Vec<u8>, String) = conn
+            .query_row(
+                "SELECT file_content, compression FROM blobs WHERE sha256 = ?1",
+                params![sha256],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+
+        assert_eq!(compression, "zstd");
+        assert!(stored_content.len() < bytes.len());
+        assert_eq!(decompress_blob(&stored_content, &compression).unwrap(), bytes);
+    }
+
+    #[test]
+    fn should_compress_skips_text_and_already_compressed_image_formats() {
+        assert!(!should_compress("text/plain"));
+        assert!(!should_compress("image/png"));
+        assert!(!should_compress("image/jpeg"));
+        assert!(!should_compress("image/webp"));
+        assert!(should_compress("application/pdf"));
+        assert!(should_compress("image/bmp"));
+    }
+
+    #[test]
+    fn store_blob_leaves_bytes_untouched_when_compress_is_disabled() {
+        let conn = test_db();
+        let bytes = b"pdf bytes that would otherwise compress".to_vec();
+
+        let sha256 = store_blob(&conn, &bytes, "application/pdf", false).unwrap();
+
+        let (stored_content, compression): (Vec<u8>, String) = conn
+            .query_row(
+                "SELECT file_content, compression FROM blobs WHERE sha256 = ?1",
+                params![sha256],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+
+        assert_eq!(compression, "none");
+        assert_eq!(stored_content, bytes);
+    }
+}
@@ -1,39 +1,226 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use std::path::PathBuf;
+use clap::{Parser, Subcommand, ValueEnum};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
 
-use importer::{create_database, extract_conversations_from_zip, import_conversations, models::Conversation};
+use importer::{
+    create_database, export_conversations, extract_conversations_from_zip, import_conversations,
+    models::Conversation, search_messages, AssetHandling, ExportFilter, ExportFormat,
+};
 
 #[derive(Parser)]
 #[command(name = "chatgpt-importer")]
-#[command(about = "Import ChatGPT conversations from zip file to SQLite database")]
+#[command(about = "Import and explore ChatGPT conversation history")]
 struct Args {
-    /// Path to the zip file containing conversations.json
-    zip_file: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Import conversations from a ChatGPT export zip into a SQLite database
+    Import {
+        /// Path to the zip file containing conversations.json
+        zip_file: PathBuf,
+
+        /// Output SQLite database file name (optional, defaults to conversations.db)
+        #[arg(short, long, default_value = "conversations.db")]
+        output: PathBuf,
+
+        /// Compress stored asset blobs (currently only "zstd" is supported)
+        #[arg(long, value_name = "ALGO", num_args = 0..=1, default_missing_value = "zstd")]
+        compress: Option<String>,
+    },
+    /// Search previously imported conversations by keyword
+    Search {
+        /// Keyword or FTS5 query to search for
+        query: String,
+
+        /// SQLite database to search (optional, defaults to conversations.db)
+        #[arg(short, long, default_value = "conversations.db")]
+        database: PathBuf,
+
+        /// Maximum number of results to return
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Render imported conversations back out as Markdown or JSON
+    Export {
+        /// SQLite database to export from (optional, defaults to conversations.db)
+        #[arg(short, long, default_value = "conversations.db")]
+        database: PathBuf,
+
+        /// Directory to write exported files into
+        #[arg(short, long, default_value = "export")]
+        output_dir: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = CliExportFormat::Markdown)]
+        format: CliExportFormat,
+
+        /// Only include messages created at or after this Unix timestamp
+        #[arg(long)]
+        since: Option<i64>,
 
-    /// Output SQLite database file name (optional, defaults to conversations.db)
-    #[arg(short, long, default_value = "conversations.db")]
-    output: PathBuf,
+        /// Only include messages created at or before this Unix timestamp
+        #[arg(long)]
+        until: Option<i64>,
+
+        /// Only include messages from this author role (e.g. "user", "assistant")
+        #[arg(long)]
+        author_role: Option<String>,
+
+        /// Include archived conversations
+        #[arg(long)]
+        include_archived: bool,
+
+        /// Only include messages on the active branch
+        #[arg(long)]
+        active_path_only: bool,
+
+        /// Extract assets as files under the output directory instead of inlining them as data: URLs
+        #[arg(long)]
+        extract_assets: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CliExportFormat {
+    Markdown,
+    Json,
+}
+
+impl std::fmt::Display for CliExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliExportFormat::Markdown => write!(f, "markdown"),
+            CliExportFormat::Json => write!(f, "json"),
+        }
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    println!("Extracting conversations.json from {}", args.zip_file.display());
-    let conversations_json = extract_conversations_from_zip(&args.zip_file)?;
+    match args.command {
+        Command::Import {
+            zip_file,
+            output,
+            compress,
+        } => run_import(&zip_file, &output, compress),
+        Command::Search {
+            query,
+            database,
+            limit,
+        } => run_search(&query, &database, limit),
+        Command::Export {
+            database,
+            output_dir,
+            format,
+            since,
+            until,
+            author_role,
+            include_archived,
+            active_path_only,
+            extract_assets,
+        } => run_export(
+            &database,
+            &output_dir,
+            format,
+            since,
+            until,
+            author_role,
+            include_archived,
+            active_path_only,
+            extract_assets,
+        ),
+    }
+}
+
+fn run_import(zip_file: &PathBuf, output: &PathBuf, compress: Option<String>) -> Result<()> {
+    let compress = match compress.as_deref() {
+        None => false,
+        Some("zstd") => true,
+        Some(other) => anyhow::bail!("Unsupported --compress algorithm: {} (only \"zstd\" is supported)", other),
+    };
+
+    println!("Extracting conversations.json from {}", zip_file.display());
+    let conversations_json = extract_conversations_from_zip(zip_file)?;
 
     println!("Parsing conversations data...");
     let conversations: Vec<Conversation> = serde_json::from_str(&conversations_json)
         .context("Failed to parse conversations.json")?;
 
-    println!("Creating SQLite database at {}", args.output.display());
-    let conn = create_database(&args.output)?;
+    println!("Creating SQLite database at {}", output.display());
+    let conn = create_database(output)?;
 
     println!("Importing {} conversations...", conversations.len());
-    import_conversations(&conn, &conversations, &args.zip_file)?;
+    import_conversations(&conn, &conversations, zip_file, compress)?;
 
     println!("Import completed successfully!");
-    println!("Database created at: {}", args.output.display());
+    println!("Database created at: {}", output.display());
+
+    Ok(())
+}
+
+fn run_search(query: &str, database: &PathBuf, limit: usize) -> Result<()> {
+    let conn = Connection::open(database)
+        .with_context(|| format!("Failed to open database: {}", database.display()))?;
+
+    let hits = search_messages(&conn, query, limit)?;
+
+    if hits.is_empty() {
+        println!("No matches for \"{}\"", query);
+        return Ok(());
+    }
+
+    for hit in hits {
+        println!(
+            "[{:.3}] conversation={} message={}\n    {}",
+            hit.rank, hit.conversation_id, hit.message_id, hit.snippet
+        );
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_export(
+    database: &Path,
+    output_dir: &Path,
+    format: CliExportFormat,
+    since: Option<i64>,
+    until: Option<i64>,
+    author_role: Option<String>,
+    include_archived: bool,
+    active_path_only: bool,
+    extract_assets: bool,
+) -> Result<()> {
+    let conn = Connection::open(database)
+        .with_context(|| format!("Failed to open database: {}", database.display()))?;
+
+    let format = match format {
+        CliExportFormat::Markdown => ExportFormat::Markdown,
+        CliExportFormat::Json => ExportFormat::Json,
+    };
+
+    let filter = ExportFilter {
+        since,
+        until,
+        author_role,
+        include_archived,
+        active_path_only,
+    };
+
+    let assets = if extract_assets {
+        AssetHandling::Extract(output_dir.join("assets"))
+    } else {
+        AssetHandling::Inline
+    };
+
+    let count = export_conversations(&conn, format, &filter, &assets, output_dir)?;
+
+    println!("Exported {} conversations to {}", count, output_dir.display());
 
     Ok(())
 }
@@ -84,6 +84,77 @@ pub struct Content {
     pub extra: HashMap<String, Value>,
 }
 
+/// A typed view over `Content` covering the `content_type` variants ChatGPT
+/// actually emits, recovered from `Content`'s flattened `extra` fields.
+/// Anything not recognized falls back to `Unknown` so callers can still use
+/// `Content::parts`.
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+#[serde(tag = "content_type", rename_all = "snake_case")]
+pub enum ContentBody {
+    Text {
+        #[serde(default)]
+        parts: Vec<Value>,
+    },
+    MultimodalText {
+        #[serde(default)]
+        parts: Vec<Value>,
+    },
+    Code {
+        #[serde(default)]
+        text: String,
+        #[serde(default)]
+        language: Option<String>,
+    },
+    ExecutionOutput {
+        #[serde(default)]
+        text: String,
+    },
+    TetherBrowsingDisplay {
+        #[serde(default)]
+        result: Option<String>,
+        #[serde(default)]
+        summary: Option<String>,
+    },
+    TetherQuote {
+        #[serde(default)]
+        text: Option<String>,
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        url: Option<String>,
+    },
+    Thoughts {
+        #[serde(default)]
+        thoughts: Vec<Thought>,
+        #[serde(default)]
+        source_analysis_msg_id: Option<String>,
+    },
+    ReasoningRecap {
+        #[serde(default)]
+        content: Option<String>,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct Thought {
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Recovers the typed `ContentBody` for a message's `Content`, falling back
+/// to `Unknown` if `content_type` isn't one we model or the shape doesn't
+/// match what we expect.
+pub fn parse_content_body(content: &Content) -> ContentBody {
+    serde_json::to_value(content)
+        .ok()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or(ContentBody::Unknown)
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct AssetPointer {
     pub asset_pointer: String,
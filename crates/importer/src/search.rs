@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+/// A single full-text search match, ranked by SQLite FTS5's bm25 score
+/// (lower is more relevant).
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub conversation_id: String,
+    pub message_id: String,
+    pub rank: f64,
+    pub snippet: String,
+}
+
+/// Queries `messages_fts` for `query` and returns up to `limit` hits ordered
+/// by relevance, each with a highlighted snippet of the matching text.
+pub fn search_messages(conn: &Connection, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+    let mut stmt = conn.prepare(
+        "SELECT conversation_id, message_id, bm25(messages_fts) AS rank, \
+         snippet(messages_fts, 4, '**', '**', '...', 10) \
+         FROM messages_fts WHERE messages_fts MATCH ?1 ORDER BY rank LIMIT ?2",
+    )?;
+
+    let hits = stmt
+        .query_map(params![query, limit as i64], |row| {
+            Ok(SearchHit {
+                conversation_id: row.get(0)?,
+                message_id: row.get(1)?,
+                rank: row.get(2)?,
+                snippet: row.get(3)?,
+            })
+        })
+        .context("Failed to run search query")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read search results")?;
+
+    Ok(hits)
+}
+
+/// Rebuilds `messages_fts` from the current contents of `messages` and
+/// `conversations`. Useful after importing into an existing database or if
+/// the index ever drifts out of sync.
+pub fn rebuild_search_index(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM messages_fts", [])
+        .context("Failed to clear messages_fts")?;
+
+    conn.execute(
+        "INSERT INTO messages_fts (conversation_id, message_id, title, author_role, text_content) \
+         SELECT m.conversation_id, m.id, c.title, m.author_role, m.text_content \
+         FROM messages m JOIN conversations c ON c.id = m.conversation_id",
+        [],
+    )
+    .context("Failed to rebuild messages_fts")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        let schema = std::fs::read_to_string("schema.sql").unwrap();
+        conn.execute_batch(&schema).unwrap();
+        conn
+    }
+
+    fn insert_message(conn: &Connection, id: &str, conversation_id: &str, text: &str) {
+        conn.execute(
+            "INSERT INTO conversations (id, title, create_time, update_time, model_slug, is_archived) \
+             VALUES (?1, 'Test Conversation', 0, 0, NULL, 0) ON CONFLICT (id) DO NOTHING",
+            params![conversation_id],
+        )
+        .unwrap();
+
+        conn.execute(
+            "INSERT INTO messages (id, conversation_id, parent_id, author_role, content_type, text_content, \
+             create_time, model_slug, message_order, has_assets, branch_id, is_on_active_path) \
+             VALUES (?1, ?2, NULL, 'user', 'text', ?3, 0, NULL, 0, 0, 0, 1)",
+            params![id, conversation_id, text],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rebuild_search_index_makes_existing_messages_searchable() {
+        let conn = test_db();
+        insert_message(&conn, "m1", "c1", "the quick brown fox");
+        insert_message(&conn, "m2", "c1", "an unrelated sentence");
+
+        rebuild_search_index(&conn).unwrap();
+
+        let hits = search_messages(&conn, "fox", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_id, "m1");
+        assert_eq!(hits[0].conversation_id, "c1");
+    }
+
+    #[test]
+    fn search_messages_respects_limit_and_returns_empty_for_no_match() {
+        let conn = test_db();
+        insert_message(&conn, "m1", "c1", "fox one");
+        insert_message(&conn, "m2", "c1", "fox two");
+        rebuild_search_index(&conn).unwrap();
+
+        let hits = search_messages(&conn, "fox", 1).unwrap();
+        assert_eq!(hits.len(), 1);
+
+        let hits = search_messages(&conn, "nonexistent", 10).unwrap();
+        assert!(hits.is_empty());
+    }
+}